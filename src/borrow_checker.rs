@@ -0,0 +1,286 @@
+//! A toy borrow checker over a miniature IR, mirroring rustc's checker in spirit:
+//! a first pass gathers loans (and move sites), a second pass replays the statement
+//! list and flags the moments where two loans conflict, or where a variable is used
+//! after being moved out.
+//!
+//! This is deliberately tiny: it does not parse real Rust, know about scopes beyond
+//! `EndScope` as a marker, or handle anything but the aliasing rules the rest of this
+//! crate demonstrates (`demo_references`, `demo_moved_ownership`). Its only job is to
+//! make those rules checkable in code instead of just readable in comments.
+
+/// A use of either a variable (by name) or a specific loan (by id).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UseTarget {
+    Var(String),
+    Loan(u32),
+}
+
+/// One statement in the toy IR.
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub enum Stmt {
+    /// Introduce a new owner variable.
+    Let(String),
+    /// Create a loan of `from`, either `&from` (`mutable: false`) or `&mut from`.
+    Borrow {
+        loan_id: u32,
+        from: String,
+        mutable: bool,
+    },
+    /// Use a variable or a loan; this is what pins down a loan's `last_use`.
+    Use(UseTarget),
+    /// Move ownership from one variable to another.
+    Move { from: String, to: String },
+    /// End the innermost scope. Not otherwise interpreted by this toy checker.
+    EndScope,
+}
+
+/// A loan recorded by `gather_loans`, with its liveness range resolved to statement
+/// indices: it is considered live from the `Borrow` statement that created it
+/// (`first_use`) through the last `Use` of that loan (`last_use`).
+#[derive(Debug, Clone)]
+pub struct Loan {
+    pub id: u32,
+    pub owner: String,
+    pub mutable: bool,
+    pub first_use: usize,
+    pub last_use: usize,
+}
+
+/// Records the statement index at which each variable was moved out of.
+#[derive(Debug, Default)]
+pub struct MoveData {
+    moved_at: std::collections::HashMap<String, usize>,
+}
+
+impl MoveData {
+    /// The index of the `Move` statement that moved `var` out, if any.
+    pub fn moved_at(&self, var: &str) -> Option<usize> {
+        self.moved_at.get(var).copied()
+    }
+}
+
+/// A conflict found by `check_loans`, naming the two offending statement indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub first_stmt: usize,
+    pub second_stmt: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "conflict between statement {} and statement {}: {}",
+            self.first_stmt, self.second_stmt, self.message
+        )
+    }
+}
+
+/// First pass: walk the statement list and record every loan's full liveness range
+/// (by scanning ahead for its last `Use`), plus every move site.
+pub fn gather_loans(stmts: &[Stmt]) -> (Vec<Loan>, MoveData) {
+    let mut loans = Vec::new();
+    let mut move_data = MoveData::default();
+
+    for (index, stmt) in stmts.iter().enumerate() {
+        match stmt {
+            Stmt::Borrow {
+                loan_id,
+                from,
+                mutable,
+            } => {
+                let last_use = stmts
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .find(|(_, s)| matches!(s, Stmt::Use(UseTarget::Loan(id)) if id == loan_id))
+                    .map(|(use_index, _)| use_index)
+                    .unwrap_or(index);
+
+                loans.push(Loan {
+                    id: *loan_id,
+                    owner: from.clone(),
+                    mutable: *mutable,
+                    first_use: index,
+                    last_use,
+                });
+            }
+            Stmt::Move { from, .. } => {
+                move_data.moved_at.insert(from.clone(), index);
+            }
+            _ => {}
+        }
+    }
+
+    (loans, move_data)
+}
+
+/// Second pass: replay the statements, keeping track of which loans are live on
+/// each owner, and report every conflict as it is introduced.
+pub fn check_loans(stmts: &[Stmt], loans: &[Loan], move_data: &MoveData) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+
+    let is_live = |loan: &Loan, at: usize| loan.first_use <= at && at <= loan.last_use;
+
+    for (index, stmt) in stmts.iter().enumerate() {
+        match stmt {
+            Stmt::Borrow {
+                loan_id, mutable, ..
+            } => {
+                let new_loan = loans.iter().find(|l| l.id == *loan_id).expect("gathered");
+
+                for other in loans {
+                    if other.id == *loan_id || other.owner != new_loan.owner {
+                        continue;
+                    }
+                    if !is_live(other, index) {
+                        continue;
+                    }
+
+                    let conflicting = *mutable || other.mutable;
+                    if conflicting {
+                        conflicts.push(Conflict {
+                            first_stmt: other.first_use,
+                            second_stmt: index,
+                            message: format!(
+                                "loan {} ({}) of `{}` overlaps live loan {} ({})",
+                                loan_id,
+                                if *mutable { "mutable" } else { "immutable" },
+                                new_loan.owner,
+                                other.id,
+                                if other.mutable {
+                                    "mutable"
+                                } else {
+                                    "immutable"
+                                },
+                            ),
+                        });
+                    }
+                }
+            }
+            Stmt::Use(UseTarget::Var(var)) => {
+                if let Some(move_index) = move_data.moved_at(var) {
+                    if move_index < index {
+                        conflicts.push(Conflict {
+                            first_stmt: move_index,
+                            second_stmt: index,
+                            message: format!("`{}` used after being moved out", var),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    conflicts
+}
+
+/// Run both passes and render a human-readable report of the conflicts found.
+pub fn diagnose(stmts: &[Stmt]) -> String {
+    let (loans, move_data) = gather_loans(stmts);
+    let conflicts = check_loans(stmts, &loans, &move_data);
+
+    if conflicts.is_empty() {
+        return "no borrow conflicts found".to_string();
+    }
+
+    conflicts
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mirrors `demo_references`: two immutable borrows of `point`, both last used
+    /// before a later `&mut` borrow is taken. Since the immutable loans' last use
+    /// precedes the mutable borrow, there should be no conflict.
+    #[test]
+    fn alias_case_from_demo_references_has_no_conflict() {
+        let stmts = vec![
+            Stmt::Let("point".to_string()),
+            Stmt::Borrow {
+                loan_id: 1,
+                from: "point".to_string(),
+                mutable: false,
+            },
+            Stmt::Borrow {
+                loan_id: 2,
+                from: "point".to_string(),
+                mutable: false,
+            },
+            Stmt::Use(UseTarget::Loan(1)),
+            Stmt::Use(UseTarget::Loan(2)),
+            Stmt::Borrow {
+                loan_id: 3,
+                from: "point".to_string(),
+                mutable: true,
+            },
+            Stmt::Use(UseTarget::Loan(3)),
+        ];
+
+        let (loans, move_data) = gather_loans(&stmts);
+        let conflicts = check_loans(&stmts, &loans, &move_data);
+
+        assert!(
+            conflicts.is_empty(),
+            "unexpected conflicts: {:?}",
+            conflicts
+        );
+    }
+
+    /// A live immutable borrow plus an overlapping `&mut` should conflict,
+    /// since the mutable loan is created before the immutable one's last use.
+    #[test]
+    fn overlapping_mutable_borrow_conflicts() {
+        let stmts = vec![
+            Stmt::Let("point".to_string()),
+            Stmt::Borrow {
+                loan_id: 1,
+                from: "point".to_string(),
+                mutable: false,
+            },
+            Stmt::Borrow {
+                loan_id: 2,
+                from: "point".to_string(),
+                mutable: true,
+            },
+            Stmt::Use(UseTarget::Loan(1)),
+            Stmt::Use(UseTarget::Loan(2)),
+        ];
+
+        let (loans, move_data) = gather_loans(&stmts);
+        let conflicts = check_loans(&stmts, &loans, &move_data);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].first_stmt, 1);
+        assert_eq!(conflicts[0].second_stmt, 2);
+    }
+
+    /// Mirrors `demo_moved_ownership`: using a variable after it has been moved
+    /// out should be flagged.
+    #[test]
+    fn use_after_move_conflicts() {
+        let stmts = vec![
+            Stmt::Let("a".to_string()),
+            Stmt::Move {
+                from: "a".to_string(),
+                to: "b".to_string(),
+            },
+            Stmt::Use(UseTarget::Var("a".to_string())),
+        ];
+
+        let (loans, move_data) = gather_loans(&stmts);
+        let conflicts = check_loans(&stmts, &loans, &move_data);
+
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].first_stmt, 1);
+        assert_eq!(conflicts[0].second_stmt, 2);
+    }
+}
@@ -7,6 +7,8 @@
 //!
 //! Examples are derived from [rust by example](https://doc.rust-lang.org/rust-by-example/scope.html)
 
+mod borrow_checker;
+mod visualize;
 
 /// Primitives are passed by value
 fn primitives_by_value(x: u32) {
@@ -185,10 +187,357 @@ fn demo_lifetime_annotations() {
     println!("\n")
 }
 
+/// A resource that announces its own release, so we can watch `Drop` run.
+struct Resource {
+    name: String,
+}
+
+impl Resource {
+    fn new(name: &str) -> Self {
+        println!("Acquiring {}", name);
+        Resource {
+            name: name.to_string(),
+        }
+    }
+}
+
+/// `Drop` is the hook the compiler calls right before a value's memory is reclaimed.
+/// Unlike `Box`, where the destructor is built in, here *we* supply the teardown logic.
+impl Drop for Resource {
+    fn drop(&mut self) {
+        println!("Releasing {}", self.name);
+    }
+}
+
+/// Show user-defined destructors via `Drop`, not just the built-in `Box` one.
+///
+/// Demo derived from [rust by example (raii section)](https://doc.rust-lang.org/rust-by-example/scope/raii.html)
+fn demo_drop_semantics() {
+    println!("===== Drop semantics =====");
+
+    // Destructors run in reverse order of declaration: `second`, then `first`.
+    {
+        let _first = Resource::new("first");
+        let _second = Resource::new("second");
+    }
+
+    // Nested scopes drop their own resources before the outer scope does.
+    {
+        let _outer = Resource::new("outer");
+        {
+            let _inner = Resource::new("inner");
+        }
+        println!("inner has already been released, outer is still alive");
+    }
+
+    // Moving a value transfers drop responsibility; the original binding drops nothing.
+    {
+        let moved_from = Resource::new("moved");
+        let moved_to = moved_from;
+
+        // `moved_from` no longer owns the resource -> below would not compile
+        //println!("{}", moved_from.name);
+
+        drop(moved_to);
+        println!("dropping moved_to released the resource exactly once");
+    }
+
+    // A loop creates and frees one `Resource` per iteration, with no manual cleanup.
+    for i in 0..1000 {
+        let _looped = Resource::new(&format!("loop-{}", i));
+    }
+
+    println!("\n")
+}
+
+/// Holds its value directly; methods on it can return owned data.
+struct Counter {
+    value: i32,
+}
+
+impl Counter {
+    /// Returns an *owned* copy, so the mutable borrow of `self` ends right here,
+    /// not at the end of the returned value's lifetime.
+    fn bump_and_copy(&mut self) -> i32 {
+        self.value += 1;
+        self.value
+    }
+}
+
+/// Holds a reference with its own lifetime parameter, tied to whatever it was built from.
+struct Wrapper<'a> {
+    value: &'a mut i32,
+}
+
+impl<'a> Wrapper<'a> {
+    /// Elided to `fn bump_and_borrow<'b>(&'b mut self) -> &'b i32`: the returned
+    /// reference's lifetime is tied to *this call's* mutable borrow of `self`, so
+    /// `self` stays mutably borrowed for as long as the returned reference is used.
+    fn bump_and_borrow(&mut self) -> &i32 {
+        *self.value += 1;
+        self.value
+    }
+}
+
+/// Show that a method returning `&'a mut self`'s data keeps `self` borrowed for as
+/// long as the returned reference lives, while a method returning an owned value
+/// releases the borrow immediately.
+///
+/// This is the non-lexical-lifetimes rule `demo_references` only hints at: "the scope
+/// of a reference ends with its last usage", applied to a borrow threaded through a
+/// method's return type.
+fn demo_lifetime_pins_borrow() {
+    println!("===== Lifetime pins the mutable borrow =====");
+
+    // `Counter::bump_and_copy` returns `i32`, an owned value, so each call's borrow
+    // of `counter` ends as soon as the call returns. Repeated calls compile fine.
+    let mut counter = Counter { value: 0 };
+    let a = counter.bump_and_copy();
+    let b = counter.bump_and_copy();
+    println!("Counter bumped to {} then {}", a, b);
+
+    // `Wrapper::bump_and_borrow` returns a reference tied to this call's `&mut self`,
+    // so the mutable borrow of `wrapped` is still alive for as long as `first` is used.
+    let mut value = 0;
+    let mut wrapped = Wrapper { value: &mut value };
+    let first = wrapped.bump_and_borrow();
+    println!("First borrow sees {}", first);
+    // first's last use was the println! above, so the borrow ends there and
+    // `wrapped` can be mutably borrowed again now.
+
+    // If we bound `first` and then tried to call `bump_and_borrow` again *before*
+    // using `first`, the compiler would reject it: `first` would still be "live"
+    // up to its last use, and that overlaps the new `&mut wrapped` the call needs.
+    //
+    //     let first = wrapped.bump_and_borrow();
+    //     let second = wrapped.bump_and_borrow(); // ERROR: cannot borrow `wrapped` as mutable
+    //     println!("{} {}", first, second);        //        more than once at a time
+    //
+    // Moving the println! for `first` up before the second call (as done above)
+    // ends `first`'s last use earlier, which is exactly why it compiles here.
+    let second = wrapped.bump_and_borrow();
+    println!("Second borrow sees {}", second);
+
+    println!("\n")
+}
+
+/// Show that Rust can move the "many readers XOR one writer" rule from compile
+/// time to runtime, using `RefCell` (checked borrows) and `Cell` (no borrows at
+/// all, just get/set of a `Copy` value).
+///
+/// Demo derived from [the standard library docs (RefCell)](https://doc.rust-lang.org/std/cell/struct.RefCell.html)
+fn demo_interior_mutability() {
+    use std::cell::{Cell, RefCell};
+
+    println!("===== Interior mutability =====");
+
+    // `Cell<i32>` sidesteps borrowing entirely: `get`/`set` copy the value in and out,
+    // so there's never an outstanding reference to check, at compile time or runtime.
+    let z = Cell::new(8);
+    z.set(z.get() + 1);
+    println!("Cell-wrapped z is now {}", z.get());
+
+    // `RefCell<Point>` enforces the same aliasing rule as `&`/`&mut`, but at runtime:
+    // many `borrow()`s can coexist, or exactly one `borrow_mut()`, never both.
+    let point = RefCell::new(Point { x: 12, y: -4, z: 8 });
+
+    {
+        let borrowed_point = point.borrow();
+        let other_borrowed_point = point.borrow();
+        println!(
+            "Point has coordinates: ({}, {}, {})",
+            borrowed_point.x, other_borrowed_point.y, borrowed_point.z
+        );
+        // Both `Ref`s are dropped at the end of this scope, so `borrow_mut()` below succeeds.
+    }
+
+    {
+        let mut mutable_borrowed_point = point.borrow_mut();
+        mutable_borrowed_point.x += 2;
+        println!(
+            "Altered Point has coordinates: ({}, {}, {})",
+            mutable_borrowed_point.x, mutable_borrowed_point.y, mutable_borrowed_point.z
+        );
+    }
+
+    // Where the compiler would have rejected an overlapping `&mut` outright, `RefCell`
+    // instead panics at runtime with "already borrowed: BorrowMutError". Guard the
+    // violation with `catch_unwind` so the demo can report the panic and recover.
+    //
+    // Silence the default panic hook for the duration of the call, so the expected
+    // panic doesn't spam stderr with a backtrace alongside our own recovery message.
+    let previous_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+
+    let outstanding_borrow = point.borrow();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _conflicting_mut_borrow = point.borrow_mut();
+    }));
+
+    std::panic::set_hook(previous_hook);
+
+    match result {
+        Ok(_) => println!("unexpectedly allowed an overlapping borrow_mut()"),
+        Err(_) => println!("recovered from the expected 'already borrowed' panic"),
+    }
+
+    drop(outstanding_borrow);
+    println!("outstanding_borrow released; borrow_mut() would succeed again now");
+
+    println!("\n")
+}
+
+/// Show shared ownership with `Rc`/`Arc`, beyond the single-owner move semantics
+/// demonstrated by `demo_moved_ownership`. Cloning an `Rc`/`Arc` does not deep-copy
+/// the value; it bumps a reference count, and the value is only freed once the
+/// last owner drops.
+///
+/// Demo derived from [rust by example (Rc)](https://doc.rust-lang.org/rust-by-example/std/rc.html)
+fn demo_shared_ownership() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    println!("===== Shared ownership =====");
+
+    let rc_point = Rc::new(Point { x: 1, y: 2, z: 3 });
+    println!(
+        "strong_count after creation: {}",
+        Rc::strong_count(&rc_point)
+    );
+
+    {
+        let _second_owner = Rc::clone(&rc_point);
+        println!(
+            "strong_count with a second owner in scope: {}",
+            Rc::strong_count(&rc_point)
+        );
+        // `_second_owner` drops here, decrementing the count again.
+    }
+
+    println!(
+        "strong_count after the second owner drops: {}",
+        Rc::strong_count(&rc_point)
+    );
+
+    // `Rc`'s counter is not atomic, so it cannot be shared across threads -> below
+    // would not compile:
+    //std::thread::spawn(move || println!("{}", rc_point.x));
+
+    // `Arc` uses an atomic counter instead, so it can be cloned into other threads.
+    let arc_point = Arc::new(Point { x: 4, y: 5, z: 6 });
+    let arc_for_first_thread = Arc::clone(&arc_point);
+    let arc_for_second_thread = Arc::clone(&arc_point);
+
+    let first_handle = std::thread::spawn(move || {
+        println!(
+            "First thread sees Arc-shared point: ({}, {}, {})",
+            arc_for_first_thread.x, arc_for_first_thread.y, arc_for_first_thread.z
+        );
+    });
+
+    let second_handle = std::thread::spawn(move || {
+        println!(
+            "Second thread sees Arc-shared point: ({}, {}, {})",
+            arc_for_second_thread.x, arc_for_second_thread.y, arc_for_second_thread.z
+        );
+    });
+
+    first_handle.join().unwrap();
+    second_handle.join().unwrap();
+    println!(
+        "strong_count after both spawned threads' owners drop: {}",
+        Arc::strong_count(&arc_point)
+    );
+
+    println!("\n")
+}
+
+/// Run the toy `borrow_checker` module over two small IR programs that mirror
+/// the aliasing examples taught elsewhere in this file: the alias case from
+/// `demo_references` (no conflict, since the immutable loans end before the
+/// mutable one begins) and a use-after-move (a conflict).
+fn demo_toy_borrow_checker() {
+    use borrow_checker::{diagnose, Stmt, UseTarget};
+
+    println!("===== Toy borrow checker =====");
+
+    let alias_case = vec![
+        Stmt::Let("point".to_string()),
+        Stmt::Borrow {
+            loan_id: 1,
+            from: "point".to_string(),
+            mutable: false,
+        },
+        Stmt::Borrow {
+            loan_id: 2,
+            from: "point".to_string(),
+            mutable: false,
+        },
+        Stmt::Use(UseTarget::Loan(1)),
+        Stmt::Use(UseTarget::Loan(2)),
+        Stmt::Borrow {
+            loan_id: 3,
+            from: "point".to_string(),
+            mutable: true,
+        },
+        Stmt::Use(UseTarget::Loan(3)),
+    ];
+    println!("alias case: {}", diagnose(&alias_case));
+
+    let use_after_move = vec![
+        Stmt::Let("a".to_string()),
+        Stmt::Move {
+            from: "a".to_string(),
+            to: "b".to_string(),
+        },
+        Stmt::Use(UseTarget::Var("a".to_string())),
+    ];
+    println!("use-after-move case: {}", diagnose(&use_after_move));
+
+    println!("\n")
+}
+
+/// The bindings and borrows from `demo_references`, described for `visualize`:
+/// where each is created, where it is last used, and whether it is mutable.
+/// Line numbers here are statement positions within that demo, counting its
+/// `let mut point` statement as 0, not file lines.
+fn demo_references_bindings() -> Vec<visualize::Binding> {
+    vec![
+        visualize::Binding::new("borrowed_point", 1, 3, false),
+        visualize::Binding::new("other_borrowed_point", 2, 3, false),
+        visualize::Binding::new("mutable_borrowed_point", 5, 7, true),
+    ]
+}
+
+/// Print an ASCII (or, with `--dot`, Graphviz) timeline of `demo_references`'s
+/// borrows, showing that the two immutable borrows' bars end before the `&mut`
+/// borrow's bar begins.
+fn run_chart(dot: bool) {
+    let bindings = demo_references_bindings();
+
+    if dot {
+        print!("{}", visualize::render_dot(&bindings));
+    } else {
+        print!("{}", visualize::render_ascii(&bindings));
+    }
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.iter().any(|a| a == "--chart") {
+        run_chart(args.iter().any(|a| a == "--dot"));
+        return;
+    }
+
     demo_raii_is_enforced();
     demo_by_value_for_primitives();
     demo_moved_ownership();
     demo_references();
     demo_lifetime_annotations();
+    demo_drop_semantics();
+    demo_lifetime_pins_borrow();
+    demo_interior_mutability();
+    demo_shared_ownership();
+    demo_toy_borrow_checker();
 }
@@ -0,0 +1,121 @@
+//! Render ASCII (or Graphviz `dot`) timelines of bindings and their borrows, so the
+//! "a reference's scope ends at its last usage, not its lexical scope" comments
+//! scattered through this crate (see `demo_references`) become an actual diagram.
+
+/// A binding or borrow to chart: it exists from `created_at` through `last_use`
+/// (both are source-line-like column indices, not byte offsets), and is drawn with
+/// `=` if immutable or `#` if it is a mutable borrow.
+pub struct Binding {
+    pub name: String,
+    pub created_at: usize,
+    pub last_use: usize,
+    pub mutable: bool,
+}
+
+impl Binding {
+    pub fn new(name: &str, created_at: usize, last_use: usize, mutable: bool) -> Self {
+        Binding {
+            name: name.to_string(),
+            created_at,
+            last_use,
+            mutable,
+        }
+    }
+}
+
+/// Render one column per line, one row per binding, with a bar spanning each
+/// binding's creation to its non-lexical last use.
+pub fn render_ascii(bindings: &[Binding]) -> String {
+    let width = bindings.iter().map(|b| b.last_use).max().unwrap_or(0) + 1;
+    let name_width = bindings.iter().map(|b| b.name.len()).max().unwrap_or(0);
+
+    let mut out = String::new();
+
+    out.push_str(&" ".repeat(name_width + 2));
+    for col in 0..width {
+        out.push_str(&format!("{}", col % 10));
+    }
+    out.push('\n');
+
+    for binding in bindings {
+        let marker = if binding.mutable { '#' } else { '=' };
+        let mut row: Vec<char> = vec![' '; width];
+        for cell in row
+            .iter_mut()
+            .take(binding.last_use + 1)
+            .skip(binding.created_at)
+        {
+            *cell = marker;
+        }
+
+        out.push_str(&format!(
+            "{:>width$}: {}\n",
+            binding.name,
+            row.into_iter().collect::<String>(),
+            width = name_width
+        ));
+    }
+
+    out
+}
+
+/// Render the same bindings as a Graphviz `dot` graph: one node per binding, with
+/// a `[created_at, last_use]` range label, as an alternative to the ASCII chart.
+pub fn render_dot(bindings: &[Binding]) -> String {
+    let mut out = String::new();
+    out.push_str("digraph lifetimes {\n");
+    out.push_str("    rankdir=LR;\n");
+    out.push_str("    node [shape=box];\n");
+
+    for binding in bindings {
+        let kind = if binding.mutable {
+            "mutable"
+        } else {
+            "immutable"
+        };
+        out.push_str(&format!(
+            "    \"{}\" [label=\"{} [{}, {}] ({})\"];\n",
+            binding.name, binding.name, binding.created_at, binding.last_use, kind
+        ));
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn immutable_bars_end_before_mutable_bar_begins() {
+        let bindings = vec![
+            Binding::new("borrowed_point", 1, 3, false),
+            Binding::new("other_borrowed_point", 2, 3, false),
+            Binding::new("mutable_borrowed_point", 5, 7, true),
+        ];
+
+        let chart = render_ascii(&bindings);
+        let bars: Vec<&str> = chart
+            .lines()
+            .skip(1)
+            .map(|line| line.split_once(": ").expect("row has a name").1)
+            .collect();
+        let last_immutable_col = bars
+            .iter()
+            .filter_map(|bar| bar.rfind('='))
+            .max()
+            .expect("an immutable bar");
+        let first_mutable_col = bars
+            .iter()
+            .filter_map(|bar| bar.find('#'))
+            .min()
+            .expect("a mutable bar");
+
+        assert_eq!(last_immutable_col, 3);
+        assert_eq!(first_mutable_col, 5);
+        assert!(last_immutable_col < first_mutable_col);
+        assert!(chart.contains('='));
+        assert!(chart.contains('#'));
+    }
+}